@@ -0,0 +1,135 @@
+//! Scheduled timers for the clock's alarms.
+//!
+//! Modeled on Trezor's `Timer`/`TimerToken`: a [`Timer`] owns the [`TimerToken`]
+//! it is waiting on, and an [`EventCtx`] acts as the registry that hands out tokens
+//! and, when polled, reports which of them have fired. The clock polls the registry
+//! once per tick, comparing the previous and current time so an alarm fires exactly
+//! when the hands sweep past its target.
+
+use chrono::{Duration, NaiveTime};
+
+/// Opaque handle identifying a scheduled timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerToken(u32);
+
+/// The firing of a scheduled timer, produced by [`EventCtx::poll`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerEvent {
+    pub token: TimerToken,
+}
+
+/// A one-shot timer holding the token it is currently waiting on.
+#[derive(Debug, Default, Clone)]
+pub struct Timer(Option<TimerToken>);
+
+impl Timer {
+    pub fn new() -> Self {
+        Timer(None)
+    }
+
+    /// Schedule the timer to fire `duration` after `from`, registering it with `ctx`.
+    pub fn start(&mut self, ctx: &mut EventCtx, from: NaiveTime, duration: Duration) {
+        self.stop(ctx);
+        self.0 = Some(ctx.register(from + duration));
+    }
+
+    /// Cancel the timer if it is running, releasing its token from `ctx`.
+    pub fn stop(&mut self, ctx: &mut EventCtx) {
+        if let Some(token) = self.0.take() {
+            ctx.cancel(token);
+        }
+    }
+
+    /// Whether `event` is the expiry of the token this timer is waiting on.
+    pub fn is_expired(&self, event: TimerEvent) -> bool {
+        self.0 == Some(event.token)
+    }
+}
+
+/// Registry of scheduled timers, polled each tick to discover which have fired.
+#[derive(Debug, Default)]
+pub struct EventCtx {
+    next: u32,
+    scheduled: Vec<(TimerToken, NaiveTime)>,
+}
+
+impl EventCtx {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a timer firing at `fire_at`, returning its fresh token.
+    pub fn register(&mut self, fire_at: NaiveTime) -> TimerToken {
+        self.next += 1;
+        let token = TimerToken(self.next);
+        self.scheduled.push((token, fire_at));
+        token
+    }
+
+    /// Drop a scheduled timer by token.
+    pub fn cancel(&mut self, token: TimerToken) {
+        self.scheduled.retain(|(t, _)| *t != token);
+    }
+
+    /// The still-pending timers as (token, fire time) pairs, for drawing dial markers.
+    pub fn pending(&self) -> impl Iterator<Item = (TimerToken, NaiveTime)> + '_ {
+        self.scheduled.iter().copied()
+    }
+
+    /// Remove and report every timer whose fire time falls in `(prev, now]`.
+    pub fn poll(&mut self, prev: NaiveTime, now: NaiveTime) -> Vec<TimerEvent> {
+        let mut fired = Vec::new();
+        self.scheduled.retain(|(token, fire_at)| {
+            if crossed(prev, now, *fire_at) {
+                fired.push(TimerEvent { token: *token });
+                false
+            } else {
+                true
+            }
+        });
+        fired
+    }
+}
+
+// Did the clock pass `target` moving from `prev` to `now`, handling the wrap past midnight?
+fn crossed(prev: NaiveTime, now: NaiveTime, target: NaiveTime) -> bool {
+    if prev <= now {
+        prev < target && target <= now
+    } else {
+        prev < target || target <= now
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::crossed;
+    use chrono::NaiveTime;
+
+    fn t(h: u32, m: u32, s: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, s).unwrap()
+    }
+
+    #[test]
+    fn test_crossed_within_window() {
+        assert!(crossed(t(9, 0, 0), t(9, 0, 2), t(9, 0, 1)));
+    }
+
+    #[test]
+    fn test_crossed_outside_window() {
+        assert!(!crossed(t(9, 0, 0), t(9, 0, 2), t(9, 0, 5)));
+    }
+
+    #[test]
+    fn test_crossed_includes_now_but_not_prev() {
+        // The target fires when it equals `now`, but not when it equals `prev`.
+        assert!(crossed(t(9, 0, 0), t(9, 0, 1), t(9, 0, 1)));
+        assert!(!crossed(t(9, 0, 0), t(9, 0, 1), t(9, 0, 0)));
+    }
+
+    #[test]
+    fn test_crossed_wraps_past_midnight() {
+        assert!(crossed(t(23, 59, 59), t(0, 0, 1), t(0, 0, 0)));
+        assert!(crossed(t(23, 59, 0), t(0, 0, 1), t(23, 59, 30)));
+        assert!(!crossed(t(23, 59, 0), t(0, 0, 1), t(12, 0, 0)));
+    }
+}
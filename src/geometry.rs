@@ -0,0 +1,91 @@
+//! Small angle/geometry helpers for the clock face.
+//!
+//! The dial uses a single convention everywhere: zero is at top dead center (12
+//! o'clock) and angles grow clockwise. [`Angle`] applies that convention once so
+//! both click detection and hand rotation can share it instead of re-deriving the
+//! quadrant math by hand.
+
+use iced::{Point, Vector};
+use std::f32::consts::PI;
+
+const TURN: f32 = 2.0 * PI;
+
+/// An angle in radians, measured clockwise from top dead center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Angle(pub f32);
+
+impl Angle {
+    /// The angle of `point` as seen from `center`.
+    ///
+    /// Uses `atan2`, which is defined in all four quadrants and on the axes - so a
+    /// click directly above or below the center no longer divides by zero.
+    pub fn from_points(center: Point, point: Point) -> Self {
+        let relative_x = point.x - center.x;
+        // Screen y grows downwards; flip it so "up" is positive like the maths.
+        let relative_y = -(point.y - center.y);
+        // `atan2` measures counter-clockwise from the +x axis; rotate into our
+        // "zero at the top, clockwise" frame and wrap into a single turn.
+        let math_angle = relative_y.atan2(relative_x);
+        Angle(((2.5 * PI) - math_angle).rem_euclid(TURN))
+    }
+
+    /// An angle a given `fraction` of a full turn clockwise from the top.
+    pub fn from_turns(fraction: f32) -> Self {
+        Angle((fraction * TURN).rem_euclid(TURN))
+    }
+
+    /// This angle as a fraction of a full turn, in `0.0..1.0`.
+    pub fn turns(self) -> f32 {
+        self.0 / TURN
+    }
+
+    /// This angle in radians.
+    pub fn radians(self) -> f32 {
+        self.0
+    }
+
+    /// A unit vector pointing along this angle.
+    pub fn to_vector(self) -> Vector {
+        Vector::new(self.0.sin(), -self.0.cos())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Angle;
+    use iced::Point;
+    use std::f32::consts::PI;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_from_points_top_is_zero() {
+        // Directly above center must not divide by zero - it is top dead center.
+        let angle = Angle::from_points(Point::new(100.0, 100.0), Point::new(100.0, 0.0));
+        assert_close(angle.radians(), 0.0);
+    }
+
+    #[test]
+    fn test_from_points_three_oclock() {
+        let angle = Angle::from_points(Point::new(100.0, 100.0), Point::new(200.0, 100.0));
+        assert_close(angle.radians(), 0.5 * PI);
+    }
+
+    #[test]
+    fn test_from_points_bottom() {
+        // Directly below center - the other axis that used to divide by zero.
+        let angle = Angle::from_points(Point::new(100.0, 100.0), Point::new(100.0, 200.0));
+        assert_close(angle.radians(), PI);
+    }
+
+    #[test]
+    fn test_from_points_nine_oclock() {
+        let angle = Angle::from_points(Point::new(100.0, 100.0), Point::new(0.0, 100.0));
+        assert_close(angle.radians(), 1.5 * PI);
+    }
+}
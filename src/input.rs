@@ -0,0 +1,58 @@
+//! Keyboard input tracking for the clock.
+//!
+//! Modeled on the `three` crate's `Input`: [`InputState`] remembers which keys are
+//! currently held, while [`InputDiff`] accumulates the keys that were hit (went from
+//! up to down) so a handler can tell a fresh press from an auto-repeat. Holding the
+//! state lets later interactions query live modifiers - for example Shift to snap
+//! minute nudges to five-minute increments.
+
+use iced::keyboard::KeyCode;
+use std::collections::HashSet;
+
+/// Keys that transitioned from up to down; emptied by [`InputDiff::clear`], not on release.
+#[derive(Debug, Default)]
+pub struct InputDiff {
+    hit: HashSet<KeyCode>,
+}
+
+impl InputDiff {
+    /// Whether `key` was hit since the diff was last cleared.
+    pub fn hit(&self, key: KeyCode) -> bool {
+        self.hit.contains(&key)
+    }
+
+    /// Forget the keys hit this frame, ready to accumulate the next frame's.
+    pub fn clear(&mut self) {
+        self.hit.clear();
+    }
+}
+
+/// The set of keys currently held down.
+#[derive(Debug, Default)]
+pub struct InputState {
+    pressed: HashSet<KeyCode>,
+}
+
+impl InputState {
+    /// Record a key press, noting it in `diff` if it was not already held.
+    pub fn press(&mut self, key: KeyCode, diff: &mut InputDiff) {
+        if self.pressed.insert(key) {
+            diff.hit.insert(key);
+        }
+    }
+
+    /// Record a key release.
+    pub fn release(&mut self, key: KeyCode) {
+        self.pressed.remove(&key);
+    }
+
+    /// Whether `key` is currently held.
+    pub fn is_pressed(&self, key: KeyCode) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    /// Whether either Shift key is held.
+    pub fn shift(&self) -> bool {
+        self.is_pressed(KeyCode::LShift) || self.is_pressed(KeyCode::RShift)
+    }
+}
@@ -1,4 +1,4 @@
-use iced::{executor, mouse, window};
+use iced::{executor, keyboard, mouse, window};
 use iced::widget::canvas::{stroke, Cache, Geometry, LineCap, Path, Stroke, Event, event};
 use iced::widget::{canvas, container};
 use iced::{
@@ -7,7 +7,14 @@ use iced::{
 };
 use chrono::prelude::*;
 use chrono::Local;
-use std::f32::consts::PI;
+
+mod alarm;
+mod geometry;
+mod input;
+use alarm::{EventCtx, Timer};
+use geometry::Angle;
+use input::{InputDiff, InputState};
+use std::time::Duration as StdDuration;
 
 const CENTER_BUTTON_RADIUS: f32 = 0.07;
 const HOUR_HAND_RADIUS: f32 = 0.7;
@@ -15,6 +22,9 @@ const MINUTE_HAND_RADIUS: f32 = 0.9;
 const SECOND_HAND_RADIUS: f32 = 0.95;
 const CLOCK_FACE_RADIUS: f32 = 1.0;
 
+// Frames the face flashes for when an alarm fires (roughly three seconds at 60fps).
+const FLASH_FRAMES: u32 = 180;
+
 const CENTER_BUTTON_REGION : CircularRegion = { CircularRegion {
     inner_radius: 0.0,
     outer_radius: CENTER_BUTTON_RADIUS
@@ -37,18 +47,112 @@ pub fn main() -> iced::Result {
     })
 }
 
+// How the clock drives its redraws.
+// - Tick wakes once a second and snaps the hands - cheap enough to leave running on battery
+// - Continuous requests a redraw every frame so the hands sweep smoothly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnimationMode {
+    Tick,
+    Continuous,
+}
+
 struct Clock {
     now: DateTime<Local>,
+    // The time the user has set. Once a hand is dragged (or nudged) the clock stops
+    // following `Local::now()` and stays frozen on this value - that frozen display is
+    // the committed time - until a center-button click resets back to the live clock.
+    override_time: Option<NaiveTime>,
+    // Pending alarms and the registry of timers backing them, polled on every tick.
+    alarms: Vec<Alarm>,
+    timers: EventCtx,
+    // Frames left in the "alarm ringing" flash; zero when the face is idle.
+    flash_frames: u32,
+    // Whether the window is currently fullscreen, toggled with the F key.
+    fullscreen: bool,
+    animation_mode: AnimationMode,
     clock: Cache,
 }
 
+/// A scheduled alarm: the time it should fire and the timer watching for it.
+struct Alarm {
+    time: NaiveTime,
+    timer: Timer,
+}
+
+/// Which hand the user grabbed when dragging on the clock face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Hand {
+    Hour,
+    Minute,
+}
+
+/// What the pointer is currently doing on the clock face.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Interaction {
+    #[default]
+    Idle,
+    Dragging { hand: Hand },
+}
+
+/// State carried by the [Clock]'s [canvas::Program]: the pointer interaction plus the
+/// accumulated keyboard input so handlers can query held keys and modifiers.
+#[derive(Debug, Default)]
+struct State {
+    interaction: Interaction,
+    // The hand last grabbed, kept after the mouse is released so the arrow keys can
+    // fine-tune it while the clock is in set-time mode.
+    selected: Option<Hand>,
+    input: InputState,
+    diff: InputDiff,
+}
+
 /// Messages handled by the [Clock] Application
 #[derive(Debug, Clone, Copy)]
 enum ClockMessage {
     Tick(DateTime<Local>),
+    // Resets the face back to live time, discarding any time the user set.
     CenterClick,
-    FaceClick(DateTime<Local>),
-    OuterClick(DateTime<Local>),
+    // Freezes the face on `NaiveTime` while the user sets it; the set time stays shown
+    // (that is the "commit") until `CenterClick` resets back to the live clock.
+    SetTime(NaiveTime),
+    OuterClick(NaiveTime),
+    AlarmFired(NaiveTime),
+    ToggleFullscreen,
+    ToggleAnimation,
+    Exit,
+}
+
+impl Clock {
+    /// The time the hands are currently showing: the edited time while setting, otherwise now.
+    fn displayed_time(&self) -> NaiveTime {
+        self.override_time
+            .unwrap_or_else(|| self.now.naive_local().time())
+    }
+
+    /// Schedule an alarm for the next occurrence of `time`, at or after now.
+    fn set_alarm(&mut self, time: NaiveTime) {
+        let now = self.now.naive_local().time();
+        let mut delta = time - now;
+        if delta < chrono::Duration::zero() {
+            // Already past today - ring at the same time tomorrow.
+            delta = delta + chrono::Duration::hours(24);
+        }
+        let duration = delta.to_std().unwrap_or(StdDuration::ZERO);
+
+        let mut timer = Timer::new();
+        timer.start(&mut self.timers, now, chrono::Duration::from_std(duration).unwrap_or_default());
+        self.alarms.push(Alarm { time, timer });
+        self.clock.clear();
+    }
+
+    // The face color, flashing between its usual blue and an alert red while an alarm rings.
+    fn face_color(&self) -> Color {
+        if self.flash_frames > 0 && (self.flash_frames / 8) % 2 == 0 {
+            Color::from_rgb8(0xE5, 0x3E, 0x3E)
+        } else {
+            Color::from_rgb8(0x12, 0x93, 0xD8)
+        }
+    }
 }
 
 impl Application for Clock {
@@ -61,6 +165,12 @@ impl Application for Clock {
         (
             Clock {
                 now: Local::now(),
+                override_time: None,
+                alarms: Vec::new(),
+                timers: EventCtx::new(),
+                flash_frames: 0,
+                fullscreen: true,
+                animation_mode: AnimationMode::Continuous,
                 clock: Default::default(),
             },
             window::change_mode(window::Id::MAIN, window::Mode::Fullscreen)
@@ -74,16 +184,65 @@ impl Application for Clock {
     fn update(&mut self, message: ClockMessage) -> Command<ClockMessage> {
         match message {
             ClockMessage::Tick(local_time) => {
-                let now = local_time;
+                // Stay frozen on the edited time while the user is setting the clock.
+                if self.override_time.is_none() && local_time != self.now {
+                    let prev = self.now.naive_local().time();
+                    let now = local_time.naive_local().time();
+                    self.now = local_time;
+
+                    // Poll the timer registry for every alarm the hands swept past this tick,
+                    // so simultaneous alarms all ring and all clear their dial markers.
+                    let mut rung = Vec::new();
+                    for event in self.timers.poll(prev, now) {
+                        if let Some(pos) = self.alarms.iter().position(|a| a.timer.is_expired(event)) {
+                            rung.push(self.alarms.remove(pos).time);
+                        }
+                    }
+                    if !rung.is_empty() {
+                        return Command::batch(
+                            rung.into_iter()
+                                .map(|time| Command::perform(async move { time }, ClockMessage::AlarmFired)),
+                        );
+                    }
 
-                if now != self.now {
-                    self.now = now;
+                    if self.flash_frames > 0 {
+                        self.flash_frames -= 1;
+                    }
                     self.clock.clear();
                 }
             }
-            ClockMessage::CenterClick => {std::process::exit(0)}
-            ClockMessage::FaceClick(time) => {println!("Face Click @{:?}", time)}
-            ClockMessage::OuterClick(time) => {println!("Outer click @{:?}", time)}
+            ClockMessage::CenterClick => {
+                // Resume following the wall clock, discarding any time being set.
+                self.override_time = None;
+                self.clock.clear();
+            }
+            ClockMessage::SetTime(time) => {
+                self.override_time = Some(time);
+                self.clock.clear();
+            }
+            ClockMessage::OuterClick(time) => self.set_alarm(time),
+            ClockMessage::AlarmFired(_time) => {
+                // Flash the face to announce the alarm.
+                self.flash_frames = FLASH_FRAMES;
+                self.clock.clear();
+            }
+            ClockMessage::ToggleFullscreen => {
+                self.fullscreen = !self.fullscreen;
+                let mode = if self.fullscreen {
+                    window::Mode::Fullscreen
+                } else {
+                    window::Mode::Windowed
+                };
+                return window::change_mode(window::Id::MAIN, mode);
+            }
+            ClockMessage::ToggleAnimation => {
+                // Drop to the once-a-second tick for low-power use, or back to a smooth sweep.
+                self.animation_mode = match self.animation_mode {
+                    AnimationMode::Continuous => AnimationMode::Tick,
+                    AnimationMode::Tick => AnimationMode::Continuous,
+                };
+            }
+            ClockMessage::Exit => std::process::exit(0),
         }
 
         Command::none()
@@ -102,9 +261,14 @@ impl Application for Clock {
     }
 
     fn subscription(&self) -> Subscription<ClockMessage> {
-        iced::time::every(std::time::Duration::from_secs(1)).map(|_| {
-            ClockMessage::Tick(Local::now())
-        })
+        match self.animation_mode {
+            AnimationMode::Tick => iced::time::every(std::time::Duration::from_secs(1))
+                .map(|_| ClockMessage::Tick(Local::now())),
+            // Redraw on every frame so the hands interpolate between seconds
+            AnimationMode::Continuous => {
+                iced::window::frames().map(|_| ClockMessage::Tick(Local::now()))
+            }
+        }
     }
 }
 
@@ -126,11 +290,11 @@ impl CircularRegion {
 }
 
 impl canvas::Program<ClockMessage> for Clock {
-    type State = ();
+    type State = State;
 
     fn update(
         &self,
-        _state: &mut Self::State,
+        state: &mut Self::State,
         event: Event,
         bounds: Rectangle,
         cursor: mouse::Cursor,
@@ -144,16 +308,79 @@ impl canvas::Program<ClockMessage> for Clock {
                     if CENTER_BUTTON_REGION.contains(cursor_radius) {
                         (event::Status::Captured, Some(ClockMessage::CenterClick))
                     } else if CLOCK_FACE_REGION.contains(cursor_radius) {
-                        let _hour = unit_from_position(bounds.center(), position, 12);
-                        (event::Status::Captured, Some(ClockMessage::FaceClick(Local::now())))
+                        // Grab whichever hand sits nearest the cursor and start dragging it.
+                        let hand = nearest_hand(self.displayed_time(), bounds.center(), position);
+                        state.interaction = Interaction::Dragging { hand };
+                        state.selected = Some(hand);
+                        (event::Status::Captured, None)
                     } else {
-                        let _hour = unit_from_position(bounds.center(), position, 12);
-                        (event::Status::Captured, Some(ClockMessage::OuterClick(Local::now())))
+                        // Outside the dial: set an alarm at the hour/minute under the cursor.
+                        let hour = unit_from_position(bounds.center(), position, 12).floor() as u32 % 12;
+                        let minute = unit_from_position(bounds.center(), position, 60).round() as u32 % 60;
+                        let selected = NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default();
+                        (event::Status::Captured, Some(ClockMessage::OuterClick(selected)))
+                    }
+                } else {
+                    (event::Status::Ignored, None)
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                if let Interaction::Dragging { hand } = state.interaction {
+                    if let Some(position) = cursor.position() {
+                        let time = set_hand(self.displayed_time(), hand, bounds.center(), position);
+                        return (event::Status::Captured, Some(ClockMessage::SetTime(time)));
                     }
+                }
+                (event::Status::Ignored, None)
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                if matches!(state.interaction, Interaction::Dragging { .. }) {
+                    state.interaction = Interaction::Idle;
+                    (event::Status::Captured, None)
                 } else {
                     (event::Status::Ignored, None)
                 }
             }
+            Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) => {
+                use keyboard::KeyCode;
+                // Record the press, then act on the keys hit this frame - auto-repeats stay
+                // out of the diff, so a held key fires its action exactly once.
+                state.input.press(key_code, &mut state.diff);
+                let message = if state.diff.hit(KeyCode::Escape) {
+                    Some(ClockMessage::Exit)
+                } else if state.diff.hit(KeyCode::F) {
+                    Some(ClockMessage::ToggleFullscreen)
+                } else if state.diff.hit(KeyCode::T) {
+                    Some(ClockMessage::ToggleAnimation)
+                } else if let (true, Some(hand)) = (self.override_time.is_some(), state.selected) {
+                    // In set-time mode the arrow keys nudge the selected hand; Shift snaps
+                    // minutes to fives. This is where fine-tuning happens, once the mouse
+                    // has been released and the edited time is frozen.
+                    let step = if state.diff.hit(KeyCode::Up) || state.diff.hit(KeyCode::Right) {
+                        Some(1)
+                    } else if state.diff.hit(KeyCode::Down) || state.diff.hit(KeyCode::Left) {
+                        Some(-1)
+                    } else {
+                        None
+                    };
+                    step.map(|step| {
+                        let time = nudge_hand(self.displayed_time(), hand, step, state.input.shift());
+                        ClockMessage::SetTime(time)
+                    })
+                } else {
+                    None
+                };
+                state.diff.clear();
+
+                match message {
+                    Some(message) => (event::Status::Captured, Some(message)),
+                    None => (event::Status::Ignored, None),
+                }
+            }
+            Event::Keyboard(keyboard::Event::KeyReleased { key_code, .. }) => {
+                state.input.release(key_code);
+                (event::Status::Ignored, None)
+            }
             _ => (event::Status::Ignored, None),
         }
     }
@@ -173,7 +400,17 @@ impl canvas::Program<ClockMessage> for Clock {
             let radius = frame.width().min(frame.height()) / 2.0;
 
             let background = Path::circle(Point::ORIGIN, radius * CLOCK_FACE_RADIUS);
-            frame.fill(&background, Color::from_rgb8(0x12, 0x93, 0xD8));
+            frame.fill(&background, self.face_color());
+
+            // Mark each pending alarm on the dial at the angle of its target time.
+            for (_token, time) in self.timers.pending() {
+                let units = (time.hour() % 12) as f32 + time.minute() as f32 / 60.0;
+                let direction = Angle::from_turns(units / 12.0).to_vector();
+                let marker_radius = radius * MINUTE_HAND_RADIUS;
+                let center = Point::new(direction.x * marker_radius, direction.y * marker_radius);
+                let marker = Path::circle(center, radius / 40.0);
+                frame.fill(&marker, Color::from_rgb8(0xF5, 0xA6, 0x23));
+            }
 
             let hour_hand =
                 Path::line(Point::ORIGIN, Point::new(0.0, - (HOUR_HAND_RADIUS * radius)));
@@ -187,8 +424,15 @@ impl canvas::Program<ClockMessage> for Clock {
                 }
             };
 
+            let time = self.displayed_time();
+            // Fractional positions so the hands sweep instead of snapping: each hand carries
+            // the remainder of the next-smaller unit (hour carries minutes, and so on).
+            let second = time.second() as f32 + time.nanosecond() as f32 / 1_000_000_000.0;
+            let minute = time.minute() as f32 + second / 60.0;
+            let hour = (time.hour() % 12) as f32 + minute / 60.0;
+
             frame.with_save(|frame| {
-                frame.rotate(hand_rotation(self.now.naive_local().time().hour() as u8, 12));
+                frame.rotate(hand_rotation(hour, 12.0));
                 frame.stroke(&hour_hand, hour_width());
             });
 
@@ -205,7 +449,7 @@ impl canvas::Program<ClockMessage> for Clock {
             };
 
             frame.with_save(|frame| {
-                frame.rotate(hand_rotation(self.now.naive_local().time().minute() as u8, 60));
+                frame.rotate(hand_rotation(minute, 60.0));
                 frame.stroke(&minute_hand, minute_width());
             });
 
@@ -222,7 +466,7 @@ impl canvas::Program<ClockMessage> for Clock {
             };
 
             frame.with_save(|frame| {
-                frame.rotate(hand_rotation(self.now.naive_local().time().second() as u8, 60));
+                frame.rotate(hand_rotation(second, 60.0));
                 frame.stroke(&second_hand, second_width());
             });
 
@@ -260,26 +504,77 @@ impl canvas::Program<ClockMessage> for Clock {
 // Calculate the unit (hour, minute, second) from a position relative to the center
 // Zero is at top dead center
 fn unit_from_position(center: Point, position: Point, total: u8) -> f32 {
-    let relative_x = position.x - center.x;
-    let relative_y = -(position.y - center.y);
-    println!("Delta X = {}, Delta Y = {}", relative_x, relative_y);
-    let div = relative_y / relative_x;
-    let mut angle = div.atan();
-    if relative_x < 0.0 {
-        angle += PI;
+    let angle = Angle::from_points(center, position);
+    let unit = total as f32 * angle.turns();
+    (unit * 1000.0).round() / 1000.0
+}
+
+// Pick the hand whose tip is closest to the cursor, so a drag grabs what the user aimed at.
+// Both hands are compared on the same 12-unit dial (the minute is scaled to hour units).
+fn nearest_hand(time: NaiveTime, center: Point, position: Point) -> Hand {
+    let cursor = unit_from_position(center, position, 12);
+    let hour = (time.hour() % 12) as f32 + time.minute() as f32 / 60.0;
+    let minute = time.minute() as f32 / 5.0;
+    if angular_gap(cursor, hour, 12.0) <= angular_gap(cursor, minute, 12.0) {
+        Hand::Hour
+    } else {
+        Hand::Minute
+    }
+}
+
+// Shortest distance between two positions on a wrap-around dial of `total` units.
+fn angular_gap(a: f32, b: f32, total: f32) -> f32 {
+    let gap = (a - b).abs() % total;
+    gap.min(total - gap)
+}
+
+// Move a single hand to the unit under the cursor, leaving the others untouched.
+// Minutes snap to whole units; the hour follows the cursor's sector.
+fn set_hand(time: NaiveTime, hand: Hand, center: Point, position: Point) -> NaiveTime {
+    match hand {
+        Hand::Hour => {
+            let hour = unit_from_position(center, position, 12).floor() as u32 % 12;
+            NaiveTime::from_hms_opt(hour, time.minute(), time.second())
+        }
+        Hand::Minute => {
+            let minute = unit_from_position(center, position, 60).round() as u32 % 60;
+            NaiveTime::from_hms_opt(time.hour(), minute, time.second())
+        }
     }
-    println!("Angle in radians {}", angle);
-    let angle = ((2.5 * PI) - angle) % (2.0 * PI);
-    println!("Corrected angle in radians {}", angle);
-    let rotation_percent = angle / (2.0 * PI);
-    (total as f32 * rotation_percent * 1000.0).round() / 1000.0
+    .unwrap_or(time)
+}
+
+// Step a single hand by `step` units, wrapping within its range. Minute steps jump by
+// five when `shift` is held so the hand snaps to whole five-minute marks.
+fn nudge_hand(time: NaiveTime, hand: Hand, step: i32, shift: bool) -> NaiveTime {
+    match hand {
+        Hand::Hour => {
+            let hour = (time.hour() as i32 + step).rem_euclid(24) as u32;
+            NaiveTime::from_hms_opt(hour, time.minute(), time.second())
+        }
+        Hand::Minute => {
+            let minute = if shift {
+                // Land on the next five-minute mark in the direction of the step.
+                let target = time.minute() as i32 + step * 5;
+                if step > 0 {
+                    target - target.rem_euclid(5)
+                } else {
+                    target + (5 - target.rem_euclid(5)) % 5
+                }
+            } else {
+                time.minute() as i32 + step
+            };
+            NaiveTime::from_hms_opt(time.hour(), minute.rem_euclid(60) as u32, time.second())
+        }
+    }
+    .unwrap_or(time)
 }
 
 // Calculate an angle (in radians) from a count over a total possible
 // e.g. 30 (minutes) over a total of 60 (minutes) is 50% of 360 degrees, or 180 degrees
-fn hand_rotation(count: u8, total: u8) -> f32 {
-    let rotation_percent = count as f32 / total as f32;
-    2.0 * PI * rotation_percent
+// Takes an f32 count so fractional positions sweep smoothly between whole units
+fn hand_rotation(count: f32, total: f32) -> f32 {
+    Angle::from_turns(count / total).radians()
 }
 
 #[cfg(test)]
@@ -328,4 +623,54 @@ mod test {
                                       Point::new(0.0,100.0),
                                       12), 9.0);
     }
+
+    use super::{nearest_hand, set_hand, nudge_hand, Hand};
+    use chrono::NaiveTime;
+
+    fn t(h: u32, m: u32, s: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, s).unwrap()
+    }
+
+    #[test]
+    fn test_nearest_hand_picks_hour() {
+        // Hour hand sits on 3, cursor is right on it.
+        let hand = nearest_hand(t(3, 0, 0), Point::new(100.0, 100.0), Point::new(200.0, 100.0));
+        assert_eq!(hand, Hand::Hour);
+    }
+
+    #[test]
+    fn test_nearest_hand_picks_minute() {
+        // At 12:15 the minute hand points at 3 o'clock while the hour hand barely left 12.
+        let hand = nearest_hand(t(0, 15, 0), Point::new(100.0, 100.0), Point::new(200.0, 100.0));
+        assert_eq!(hand, Hand::Minute);
+    }
+
+    #[test]
+    fn test_set_hand_hour_keeps_other_fields() {
+        let set = set_hand(t(10, 20, 30), Hand::Hour, Point::new(100.0, 100.0), Point::new(200.0, 100.0));
+        assert_eq!(set, t(3, 20, 30));
+    }
+
+    #[test]
+    fn test_set_hand_minute_keeps_other_fields() {
+        let set = set_hand(t(10, 20, 30), Hand::Minute, Point::new(100.0, 100.0), Point::new(100.0, 200.0));
+        assert_eq!(set, t(10, 30, 30));
+    }
+
+    #[test]
+    fn test_nudge_hand_hour_wraps_at_24() {
+        assert_eq!(nudge_hand(t(23, 30, 0), Hand::Hour, 1, false), t(0, 30, 0));
+        assert_eq!(nudge_hand(t(0, 30, 0), Hand::Hour, -1, false), t(23, 30, 0));
+    }
+
+    #[test]
+    fn test_nudge_hand_minute_wraps_at_60() {
+        assert_eq!(nudge_hand(t(10, 59, 0), Hand::Minute, 1, false), t(10, 0, 0));
+        assert_eq!(nudge_hand(t(10, 0, 0), Hand::Minute, -1, false), t(10, 59, 0));
+    }
+
+    #[test]
+    fn test_nudge_hand_minute_shift_snaps_to_five() {
+        assert_eq!(nudge_hand(t(10, 0, 0), Hand::Minute, 1, true), t(10, 5, 0));
+    }
 }
\ No newline at end of file